@@ -1,5 +1,96 @@
 use crate::{Meta, Named};
 
+/// Word split out of a help string, width measured with [`unicode_width`]
+struct Word<'a> {
+    text: &'a str,
+    width: usize,
+}
+
+/// Re-wraps a help string to `width` columns with an optimal-fit line breaker, preserving
+/// `'\n'` as a hard break
+fn wrap_help(help: &str, width: usize) -> Vec<String> {
+    help.split('\n')
+        .flat_map(|segment| wrap_paragraph(segment, width))
+        .collect()
+}
+
+/// Knuth-Plass style DP: `best[i]` is the minimal total slack-squared cost of breaking the
+/// first `i` words into lines, so the optimizer favors evenly filled lines over greedy fill.
+/// A single word wider than `width` still gets its own line since there's no better option.
+fn wrap_paragraph(paragraph: &str, width: usize) -> Vec<String> {
+    let words = paragraph
+        .split_whitespace()
+        .map(|text| Word {
+            text,
+            width: unicode_width::UnicodeWidthStr::width(text),
+        })
+        .collect::<Vec<_>>();
+
+    if words.is_empty() {
+        return vec![String::new()];
+    }
+
+    let n = words.len();
+    const INF: usize = usize::MAX / 2;
+    let mut best = vec![INF; n + 1];
+    let mut from = vec![0usize; n + 1];
+    best[0] = 0;
+
+    for i in 1..=n {
+        let mut used = 0usize;
+        for j in (0..i).rev() {
+            used += words[j].width;
+            if j != i - 1 {
+                used += 1; // space before the word that follows
+            }
+            let overflows = used > width;
+            if overflows && j != i - 1 {
+                // adding more words only grows `used` further, no point continuing
+                break;
+            }
+            if best[j] == INF {
+                continue;
+            }
+            let cost = if i == n || overflows {
+                // last line of the paragraph, or a lone word too wide to fit: free
+                0
+            } else {
+                let slack = width - used;
+                slack * slack
+            };
+            let total = best[j] + cost;
+            if total < best[i] {
+                best[i] = total;
+                from[i] = j;
+            }
+        }
+    }
+
+    let mut breaks = Vec::new();
+    let mut i = n;
+    while i > 0 {
+        breaks.push((from[i], i));
+        i = from[i];
+    }
+    breaks.reverse();
+
+    breaks
+        .into_iter()
+        .map(|(start, end)| {
+            words[start..end]
+                .iter()
+                .map(|w| w.text)
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect()
+}
+
+/// Current terminal width in columns, falling back to 80 when it can't be detected
+fn terminal_width() -> usize {
+    terminal_size::terminal_size().map_or(80, |(w, _)| w.0 as usize)
+}
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
 enum ItemKind {
     Flag,
@@ -8,6 +99,65 @@ enum ItemKind {
     Positional,
 }
 
+/// Named slot a [`HelpTemplate`] placeholder fills in.
+#[doc(hidden)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Section {
+    Positionals,
+    Options,
+    Commands,
+}
+
+/// A reordered/relabeled help layout, written as a string with `{usage}`, `{positionals}`,
+/// `{options}`, `{commands}` and `{env}` placeholders; anything else is copied through
+/// verbatim, so a consumer can add a custom banner or move commands before options without
+/// forking the formatter.
+#[doc(hidden)]
+#[derive(Clone, Debug)]
+pub struct HelpTemplate(String);
+
+impl Default for HelpTemplate {
+    /// The layout this template mechanism replaces: usage, then positionals, options and
+    /// commands in that order.
+    fn default() -> Self {
+        HelpTemplate("{usage}\n\n{positionals}{options}{commands}".to_string())
+    }
+}
+
+impl HelpTemplate {
+    #[must_use]
+    pub fn new<S: Into<String>>(template: S) -> Self {
+        HelpTemplate(template.into())
+    }
+
+    /// Renders `usage` and `items` (aligned to `width`, see `Display for Item`'s `{:#}`
+    /// form) through the template, substituting each placeholder with the items in its
+    /// [`Section`] — or, for `{env}`, every [`Item::Argument`] that carries an `env`.
+    pub(crate) fn render(&self, usage: &str, items: &[Item], width: usize) -> String {
+        let group = |section: Section| -> String {
+            items
+                .iter()
+                .filter(|item| item.section() == Some(section))
+                .map(|item| format!("{:#width$}\n", item, width = width))
+                .collect()
+        };
+        let env = || -> String {
+            items
+                .iter()
+                .filter(|item| item.has_env())
+                .map(|item| format!("{:#width$}\n", item, width = width))
+                .collect()
+        };
+
+        self.0
+            .replace("{usage}", usage)
+            .replace("{positionals}", &group(Section::Positionals))
+            .replace("{options}", &group(Section::Options))
+            .replace("{commands}", &group(Section::Commands))
+            .replace("{env}", &env())
+    }
+}
+
 #[doc(hidden)]
 #[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
 pub enum Item {
@@ -26,12 +176,14 @@ pub enum Item {
     Flag {
         name: ShortLong,
         help: Option<String>,
+        default: Option<String>,
     },
     Argument {
         name: ShortLong,
         metavar: &'static str,
         env: Option<&'static str>,
         help: Option<String>,
+        default: Option<String>,
     },
 }
 
@@ -101,6 +253,49 @@ impl std::fmt::Display for ShortLong {
     }
 }
 
+/// Whether `--help` output is decorated with ANSI styling, set via [`set_color_choice`]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Default)]
+pub enum ColorChoice {
+    /// Enable styling only when the output stream is a terminal
+    #[default]
+    Auto,
+    /// Always emit ANSI escapes, even when the output is piped
+    Always,
+    /// Never emit ANSI escapes, the current plain-text behavior
+    Never,
+}
+
+thread_local! {
+    static COLOR_CHOICE: std::cell::Cell<ColorChoice> = std::cell::Cell::new(ColorChoice::default());
+}
+
+/// Sets the [`ColorChoice`] used by subsequent `{:#}` renders of [`Item`] on this thread
+pub(crate) fn set_color_choice(choice: ColorChoice) {
+    COLOR_CHOICE.with(|c| c.set(choice));
+}
+
+fn color_enabled() -> bool {
+    match COLOR_CHOICE.with(std::cell::Cell::get) {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => std::io::IsTerminal::is_terminal(&std::io::stdout()),
+    }
+}
+
+const STYLE_NAME: &str = "1"; // bold
+const STYLE_METAVAR: &str = "2"; // dim
+const STYLE_DECOR: &str = "1;4"; // bold + underline, used for section headers
+const STYLE_ENV: &str = "32"; // green, used for values pulled from the environment
+
+/// Wraps `text` in the ANSI escapes for `code` when styling is enabled
+fn styled(text: &str, code: &str) -> String {
+    if color_enabled() {
+        format!("\u{1b}[{}m{}\u{1b}[0m", code, text)
+    } else {
+        text.to_string()
+    }
+}
+
 /// {} renders shorter version that can be used in a short usage string
 /// {:#} renders a full width version that can be used in --help body and complete, this version
 /// supports padding of the help by some max width
@@ -109,67 +304,98 @@ impl std::fmt::Display for Item {
         // alternate version is used to render the option list
         if f.alternate() {
             match self {
-                Item::Flag { name, help: _ } => write!(f, "    {:#}", name),
-                Item::Argument {
-                    name,
-                    metavar,
-                    help: _,
-                    env,
-                } => {
-                    write!(f, "    {:#} <{}>", name, metavar)?;
-
-                    if let Some((width, env)) = f.width().zip(*env) {
-                        let pad = width - self.full_width();
-                        let val = match std::env::var(env) {
-                            Ok(val) => format!(" = {:?}", val),
-                            Err(std::env::VarError::NotPresent) => ": N/A".to_string(),
-                            Err(std::env::VarError::NotUnicode(_)) => {
-                                ": current value is not utf8".to_string()
-                            }
-                        };
-                        let next_pad = 4 + self.full_width();
-                        write!(
-                            f,
-                            "{:pad$}  [env:{}{}]\n{:width$}",
-                            "",
-                            env,
-                            val,
-                            "",
-                            pad = pad,
-                            width = next_pad,
-                        )?;
-                    }
-                    Ok(())
+                Item::Flag { name, .. } => {
+                    write!(f, "    {}", styled(&format!("{:#}", name), STYLE_NAME))
                 }
+                Item::Argument { name, metavar, .. } => write!(
+                    f,
+                    "    {} <{}>",
+                    styled(&format!("{:#}", name), STYLE_NAME),
+                    styled(metavar, STYLE_METAVAR)
+                ),
                 Item::Decor { help } => {
                     if help.is_some() {
                         write!(f, "    ")?
                     }
                     Ok(())
                 }
-                Item::Positional { metavar, help: _ } => write!(f, "    <{}>", metavar),
+                Item::Positional { metavar, help: _ } => {
+                    write!(f, "    <{}>", styled(metavar, STYLE_METAVAR))
+                }
                 Item::Command {
                     name,
                     help: _,
                     short,
                 } => match short {
-                    Some(s) => write!(f, "    {}, {}", name, s),
-                    None => write!(f, "    {}", name),
+                    Some(s) => write!(f, "    {}, {}", styled(name, STYLE_NAME), s),
+                    None => write!(f, "    {}", styled(name, STYLE_NAME)),
                 },
             }?;
 
-            // width must be specified on the top level
+            // `width` is the aligned help column, computed by the caller from only the
+            // items whose `full_width` fits under its adaptive cap. `precision`, when
+            // given, is the fallback indent to use for the items that didn't: rather than
+            // push every other item's help far to the right to accommodate one long name,
+            // those items get their help rendered on the following line instead.
             let width = f.width().unwrap();
+            let overlong = self.full_width() > width;
+            let help_col = if overlong {
+                f.precision().unwrap_or(width + 4)
+            } else {
+                width
+            };
+
+            for (note, style) in self.notes() {
+                let note = styled(&format!("[{}]", note), style);
+                if overlong {
+                    write!(f, "\n{:help_col$}{}", "", note, help_col = help_col)?;
+                } else {
+                    let pad = width - self.full_width();
+                    let next_pad = 4 + self.full_width();
+                    write!(
+                        f,
+                        "{:pad$}  {}\n{:width$}",
+                        "",
+                        note,
+                        "",
+                        pad = pad,
+                        width = next_pad
+                    )?;
+                }
+            }
+
             if let Some(help) = self.help() {
-                let pad = width - self.full_width();
-                for (ix, line) in help.split('\n').enumerate() {
+                // Non-overlong items write `pad + 2` spaces before the text on top of the
+                // `4`-space item indent the name itself already consumed, so the text
+                // actually lands at column `help_col + 6`; overlong items write `help_col`
+                // spaces directly with no extra indent on top.
+                let text_col = if overlong { help_col } else { help_col + 6 };
+                let content_width = terminal_width().saturating_sub(text_col).max(10);
+                let decor_style = matches!(self, Item::Decor { .. });
+                let style_line = |line: &String| {
+                    if decor_style {
+                        styled(line, STYLE_DECOR)
+                    } else {
+                        line.clone()
+                    }
+                };
+                if overlong {
+                    for line in wrap_help(help, content_width).iter().map(style_line) {
+                        write!(f, "\n{:help_col$}{}", "", line, help_col = help_col)?;
+                    }
+                } else {
+                    let pad = width - self.full_width();
+                    for (ix, line) in wrap_help(help, content_width)
+                        .iter()
+                        .map(style_line)
+                        .enumerate()
                     {
                         if ix == 0 {
                             write!(f, "{:pad$}  {}", "", line, pad = pad)
                         } else {
                             write!(f, "\n{:pad$}      {}", "", line, pad = width)
-                        }
-                    }?
+                        }?
+                    }
                 }
             }
             Ok(())
@@ -179,13 +405,8 @@ impl std::fmt::Display for Item {
                 Item::Decor { .. } => Ok(()),
                 Item::Positional { metavar, help: _ } => write!(f, "<{}>", metavar),
                 Item::Command { .. } => write!(f, "COMMAND ..."),
-                Item::Flag { name, help: _ } => write!(f, "{}", name),
-                Item::Argument {
-                    name,
-                    metavar,
-                    help: _,
-                    env: _,
-                } => write!(f, "{} {}", name, metavar),
+                Item::Flag { name, .. } => write!(f, "{}", name),
+                Item::Argument { name, metavar, .. } => write!(f, "{} {}", name, metavar),
             }
         }
     }
@@ -214,6 +435,31 @@ impl Item {
         }
     }
 
+    /// Declared default value, if this `Flag`/`Argument` has one
+    fn default(&self) -> Option<&str> {
+        match self {
+            Item::Flag { default, .. } | Item::Argument { default, .. } => default.as_deref(),
+            Item::Decor { .. } | Item::Positional { .. } | Item::Command { .. } => None,
+        }
+    }
+
+    /// `[env:...]`/`[default: ...]` footnotes for this item, each paired with its ANSI style
+    fn notes(&self) -> Vec<(String, &'static str)> {
+        let mut notes = Vec::new();
+        if let Item::Argument { env: Some(env), .. } = self {
+            let val = match std::env::var(env) {
+                Ok(val) => format!(" = {:?}", val),
+                Err(std::env::VarError::NotPresent) => ": N/A".to_string(),
+                Err(std::env::VarError::NotUnicode(_)) => ": current value is not utf8".to_string(),
+            };
+            notes.push((format!("env:{}{}", env, val), STYLE_ENV));
+        }
+        if let Some(default) = self.default() {
+            notes.push((format!("default: {}", default), STYLE_METAVAR));
+        }
+        notes
+    }
+
     #[must_use]
     pub(crate) fn decoration<M>(help: Option<M>) -> Self
     where
@@ -247,4 +493,145 @@ impl Item {
             ItemKind::Flag | ItemKind::Decor | ItemKind::Command => false,
         }
     }
+
+    /// Template placeholder this item belongs to, if any
+    #[must_use]
+    pub(crate) fn section(&self) -> Option<Section> {
+        match self.kind() {
+            ItemKind::Positional => Some(Section::Positionals),
+            ItemKind::Flag => Some(Section::Options),
+            ItemKind::Command => Some(Section::Commands),
+            ItemKind::Decor => None,
+        }
+    }
+
+    /// Whether this item should also show up under a template's `{env}` placeholder
+    #[must_use]
+    pub(crate) fn has_env(&self) -> bool {
+        matches!(self, Item::Argument { env: Some(_), .. })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line_width(line: &str) -> usize {
+        unicode_width::UnicodeWidthStr::width(line)
+    }
+
+    #[test]
+    fn wrap_paragraph_keeps_every_line_within_width() {
+        let text = "the quick brown fox jumps over the lazy dog and then keeps going";
+        for line in wrap_paragraph(text, 12) {
+            assert!(line_width(&line) <= 12, "{:?} overflows 12 columns", line);
+        }
+    }
+
+    #[test]
+    fn wrap_paragraph_preserves_all_words_in_order() {
+        let text = "one two three four five six seven";
+        let rewrapped = wrap_paragraph(text, 10).join(" ");
+        assert_eq!(rewrapped, text);
+    }
+
+    #[test]
+    fn notes_renders_declared_default() {
+        let item = Item::Flag {
+            name: ShortLong::Long("quiet"),
+            help: None,
+            default: Some("false".to_string()),
+        };
+        let notes: Vec<_> = item.notes().into_iter().map(|(note, _)| note).collect();
+        assert_eq!(notes, vec!["default: false"]);
+    }
+
+    #[test]
+    fn notes_renders_env_and_default_together() {
+        std::env::remove_var("BPAF_TEST_NOTES_PORT");
+        let item = Item::Argument {
+            name: ShortLong::Long("port"),
+            metavar: "PORT",
+            env: Some("BPAF_TEST_NOTES_PORT"),
+            help: None,
+            default: Some("8080".to_string()),
+        };
+        let notes: Vec<_> = item.notes().into_iter().map(|(note, _)| note).collect();
+        assert_eq!(
+            notes,
+            vec!["env:BPAF_TEST_NOTES_PORT: N/A", "default: 8080"]
+        );
+    }
+
+    #[test]
+    fn notes_empty_without_env_or_default() {
+        let item = Item::Positional {
+            metavar: "FILE",
+            help: None,
+        };
+        assert!(item.notes().is_empty());
+    }
+
+    #[test]
+    fn wrap_paragraph_allows_a_single_overlong_word_on_its_own_line() {
+        let text = "short antidisestablishmentarianism short";
+        let lines = wrap_paragraph(text, 10);
+        assert!(lines.iter().any(|l| l == "antidisestablishmentarianism"));
+    }
+
+    #[test]
+    fn wrap_help_preserves_hard_breaks() {
+        let lines = wrap_help("first paragraph\nsecond paragraph", 80);
+        assert_eq!(lines, vec!["first paragraph", "second paragraph"]);
+    }
+
+    #[test]
+    fn template_groups_items_into_their_sections() {
+        set_color_choice(ColorChoice::Never);
+        let items = vec![
+            Item::Positional {
+                metavar: "FILE",
+                help: None,
+            },
+            Item::Flag {
+                name: ShortLong::Long("verbose"),
+                help: None,
+                default: None,
+            },
+            Item::Command {
+                name: "run",
+                short: None,
+                help: None,
+            },
+        ];
+
+        let rendered = HelpTemplate::default().render("prog [OPTIONS]", &items, 10);
+
+        assert!(rendered.starts_with("prog [OPTIONS]\n\n"));
+        assert!(rendered.contains("<FILE>"));
+        assert!(rendered.contains("--verbose"));
+        assert!(rendered.contains("run"));
+    }
+
+    #[test]
+    fn template_env_placeholder_only_lists_items_with_env() {
+        let items = vec![
+            Item::Argument {
+                name: ShortLong::Long("port"),
+                metavar: "PORT",
+                env: Some("APP_PORT"),
+                help: None,
+                default: None,
+            },
+            Item::Flag {
+                name: ShortLong::Long("quiet"),
+                help: None,
+                default: None,
+            },
+        ];
+
+        let rendered = HelpTemplate::new("{env}").render("", &items, 10);
+        assert!(rendered.contains("APP_PORT"));
+        assert!(!rendered.contains("--quiet"));
+    }
 }